@@ -0,0 +1,121 @@
+use crate::point::PointNDRf64;
+use std::collections::HashSet;
+
+/// Tolerance used to treat two point components as equal for the purposes of
+/// the hashed dataset lookup below. This exists only to absorb floating-point
+/// rounding (e.g. `0.1 + 0.2 != 0.3`) — it is not a user-facing musical
+/// tolerance. `min_ratio` is the tolerance knob callers actually want, for
+/// missing or extra notes.
+const EPSILON: f64 = 1e-6;
+
+/// A quantized key for hashed, epsilon-tolerant point lookups: each component
+/// is rounded to the nearest multiple of `EPSILON`, so components that differ
+/// only by rounding noise share a key. Two components closer than `EPSILON`
+/// that fall either side of a rounding boundary still get different keys.
+fn quantize(point: &PointNDRf64) -> Vec<i64> {
+    (0..point.dimensions())
+        .map(|c| (point.component_f64(c).unwrap() / EPSILON).round() as i64)
+        .collect()
+}
+
+/// A translational occurrence of the query pattern: the translated query
+/// points whose quantized key was found in the dataset, and the fraction of
+/// the query that was actually found.
+pub struct Occurrence {
+    pub points: Vec<PointNDRf64>,
+    pub ratio: f64,
+}
+
+/// Translational partial-match search: for each dataset point `d`, treats
+/// `d - query[0]` as a candidate translation and counts how many translated
+/// query points land on a dataset point. Emits an [`Occurrence`] whenever the
+/// matched fraction is at least `min_ratio`, deduplicating occurrences that
+/// arise from the same translation vector.
+///
+/// `query` must be non-empty.
+pub fn find_occurrences_approx(
+    query: &[PointNDRf64],
+    dataset: &[PointNDRf64],
+    min_ratio: f64,
+) -> Vec<Occurrence> {
+    let dataset_lookup: HashSet<Vec<i64>> = dataset.iter().map(quantize).collect();
+    let first_query_point = &query[0];
+
+    let mut seen_translations: HashSet<Vec<i64>> = HashSet::new();
+    let mut occurrences = Vec::new();
+
+    for d in dataset {
+        let translation = d.difference(first_query_point);
+
+        if !seen_translations.insert(quantize(&translation)) {
+            continue;
+        }
+
+        let matched_points: Vec<PointNDRf64> = query
+            .iter()
+            .map(|q| q.translate(&translation))
+            .filter(|candidate| dataset_lookup.contains(&quantize(candidate)))
+            .collect();
+
+        let ratio = matched_points.len() as f64 / query.len() as f64;
+
+        if ratio >= min_ratio {
+            occurrences.push(Occurrence { points: matched_points, ratio });
+        }
+    }
+
+    occurrences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(components: &[f64]) -> PointNDRf64 {
+        PointNDRf64::new(components)
+    }
+
+    #[test]
+    fn exact_match_has_ratio_one() {
+        let query = vec![point(&[0.0, 0.0]), point(&[1.0, 0.0])];
+        let dataset = vec![point(&[5.0, 5.0]), point(&[6.0, 5.0]), point(&[10.0, 10.0])];
+
+        let occurrences = find_occurrences_approx(&query, &dataset, 1.0);
+
+        assert_eq!(occurrences.len(), 1);
+        assert_eq!(occurrences[0].ratio, 1.0);
+        assert_eq!(occurrences[0].points, vec![point(&[5.0, 5.0]), point(&[6.0, 5.0])]);
+    }
+
+    #[test]
+    fn partial_match_respects_min_ratio() {
+        let query = vec![point(&[0.0, 0.0]), point(&[1.0, 0.0]), point(&[2.0, 0.0])];
+        let dataset = vec![point(&[5.0, 5.0]), point(&[6.0, 5.0])];
+
+        let occurrences = find_occurrences_approx(&query, &dataset, 0.5);
+        assert_eq!(occurrences.len(), 1);
+        assert!((occurrences[0].ratio - 2.0 / 3.0).abs() < 1e-12);
+
+        let none = find_occurrences_approx(&query, &dataset, 0.8);
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn duplicate_translations_are_deduplicated() {
+        let query = vec![point(&[0.0, 0.0])];
+        let dataset = vec![point(&[1.0, 1.0]), point(&[1.0, 1.0]), point(&[2.0, 2.0])];
+
+        let occurrences = find_occurrences_approx(&query, &dataset, 1.0);
+        assert_eq!(occurrences.len(), 2);
+    }
+
+    #[test]
+    fn floating_point_rounding_does_not_block_a_match() {
+        // 0.1 + 0.2 != 0.3 in f64; the hashed lookup must still treat these as equal.
+        let query = vec![point(&[0.1, 0.0]), point(&[0.2, 0.0])];
+        let dataset = vec![point(&[0.1 + 0.2, 0.0]), point(&[0.2 + 0.2, 0.0])];
+
+        let occurrences = find_occurrences_approx(&query, &dataset, 1.0);
+        assert_eq!(occurrences.len(), 1);
+    }
+}
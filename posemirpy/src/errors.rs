@@ -0,0 +1,83 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::{create_exception, PyErr};
+
+/// Raised when the numpy input passed across the FFI boundary cannot be
+/// interpreted as a valid point set (wrong rank, too few columns, empty, ...).
+create_exception!(posemirpy, PosemirInputError, PyValueError);
+
+/// Checks that an array has exactly two dimensions (rows, columns).
+pub fn require_2d(ndim: usize) -> Result<(), PyErr> {
+    if ndim != 2 {
+        return Err(PosemirInputError::new_err(format!(
+            "expected a 2-D array, got {ndim} dimension(s)"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks that an array has at least one row.
+pub fn require_non_empty(rows: usize) -> Result<(), PyErr> {
+    if rows == 0 {
+        return Err(PosemirInputError::new_err("expected a non-empty point array"));
+    }
+
+    Ok(())
+}
+
+/// Checks that an array has enough columns to satisfy the requested column mapping.
+pub fn require_columns(cols: usize, required: usize) -> Result<(), PyErr> {
+    if cols < required {
+        return Err(PosemirInputError::new_err(format!(
+            "expected at least {required} column(s), got {cols}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks that a numeric parameter is strictly positive.
+pub fn require_positive(value: f64, name: &str) -> Result<(), PyErr> {
+    if value <= 0.0 {
+        return Err(PosemirInputError::new_err(format!(
+            "{name} must be positive, got {value}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks that the requested number of point dimensions fits within what
+/// `PointNDRf64` can hold.
+pub fn require_max_dimensions(dimensions: usize, max_dimensions: usize) -> Result<(), PyErr> {
+    if dimensions > max_dimensions {
+        return Err(PosemirInputError::new_err(format!(
+            "requested {dimensions} dimensions, but at most {max_dimensions} are supported"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks that a column mapping selects at least one column, i.e. points have
+/// at least one dimension.
+pub fn require_non_empty_columns(column_indices: &[usize]) -> Result<(), PyErr> {
+    if column_indices.is_empty() {
+        return Err(PosemirInputError::new_err(
+            "column_indices must select at least one column",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks that a ratio parameter lies in the left-open interval (0, 1].
+pub fn require_ratio(value: f64, name: &str) -> Result<(), PyErr> {
+    if value <= 0.0 || value > 1.0 {
+        return Err(PosemirInputError::new_err(format!(
+            "{name} must be in the range (0, 1], got {value}"
+        )));
+    }
+
+    Ok(())
+}
@@ -1,28 +1,74 @@
+mod approx;
+mod errors;
+mod point;
+
+use errors::{
+    require_2d, require_columns, require_max_dimensions, require_non_empty,
+    require_non_empty_columns, require_positive, require_ratio, PosemirInputError,
+};
 use numpy::{PyArray2, PyReadonlyArrayDyn};
+use point::{PointNDRf64, MAX_DIMENSIONS};
 use posemir::discovery::algorithm::TecAlgorithm;
+use posemir::discovery::cosiatec::Cosiatec;
+use posemir::discovery::siatec::Siatec;
 use posemir::discovery::siatec_c::SiatecC;
 use posemir::point_set::pattern::Pattern;
-use posemir::point_set::point::{Point, Point2DRf64};
+use posemir::point_set::point::Point;
 use posemir::point_set::set::PointSet;
 use posemir::point_set::tec::Tec;
 use posemir::search::pattern_matcher::{ExactMatcher, PatternMatcher};
-use pyo3::{pymodule, types::PyModule, PyResult, Python};
+use pyo3::{pymodule, types::PyModule, PyErr, PyObject, PyResult, Python};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
 
+/// The default column mapping, preserved for backwards compatibility with the
+/// original 2-D (onset, pitch) layout: x is read from column 2, y from column 1.
+const DEFAULT_COLUMN_INDICES: [usize; 2] = [2, 1];
 
 /// The Python module definition
 #[pymodule]
-fn posemirpy(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+fn posemirpy(py: Python<'_>, m: &PyModule) -> PyResult<()> {
 
-    fn pattern_to_array<'py>(py: Python<'py>, pattern: &Pattern<Point2DRf64>) -> &'py PyArray2<f64> {
+    fn pattern_to_array<'py>(py: Python<'py>, pattern: &Pattern<PointNDRf64>, dimensions: usize) -> &'py PyArray2<f64> {
         let arr = unsafe {
             let rows = pattern.len();
-            let cols = 2;
-            let arr = PyArray2::<f64>::new(py, [rows, cols], false);
+            let arr = PyArray2::<f64>::new(py, [rows, dimensions], false);
 
             for i in 0..rows {
                 let p = pattern[i];
-                arr.uget_raw([i, 0]).write(p.get_raw_x());
-                arr.uget_raw([i, 1]).write(p.component_f64(1).unwrap());
+                for c in 0..dimensions {
+                    arr.uget_raw([i, c]).write(p.component_f64(c).unwrap());
+                }
+            }
+
+            arr
+        };
+
+        arr
+    }
+
+    // Plain Rust representation of a pattern that does not touch `py`, so it can be
+    // built while the GIL is released.
+    fn pattern_to_raw(pattern: &Pattern<PointNDRf64>, dimensions: usize) -> Vec<Vec<f64>> {
+        let mut raw = Vec::with_capacity(pattern.len());
+
+        for i in 0..pattern.len() {
+            let p = pattern[i];
+            raw.push((0..dimensions).map(|c| p.component_f64(c).unwrap()).collect());
+        }
+
+        raw
+    }
+
+    fn raw_to_array<'py>(py: Python<'py>, raw: &[Vec<f64>], dimensions: usize) -> &'py PyArray2<f64> {
+        let arr = unsafe {
+            let rows = raw.len();
+            let arr = PyArray2::<f64>::new(py, [rows, dimensions], false);
+
+            for (i, p) in raw.iter().enumerate() {
+                for c in 0..dimensions {
+                    arr.uget_raw([i, c]).write(p[c]);
+                }
             }
 
             arr
@@ -31,65 +77,292 @@ fn posemirpy(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
         arr
     }
 
-    fn numpy_array_to_points(np_array: &PyReadonlyArrayDyn<f64>) -> Vec<Point2DRf64> {
-        let mut points : Vec<Point2DRf64> = Vec::new();
+    // A discovered TEC collected without holding the GIL: the pattern and each of
+    // its translated occurrences as plain point buffers.
+    struct RawTec {
+        pattern: Vec<Vec<f64>>,
+        translated_patterns: Vec<Vec<Vec<f64>>>,
+    }
+
+    // Runs any `TecAlgorithm` and collects its output as plain buffers, reporting
+    // progress via `progress_callback` along the way. `compute_tecs_to_output`'s
+    // `on_output` contract returns `()`, so posemir gives no way to actually abort
+    // the algorithm early: once `progress_callback` returns `False` (or raises),
+    // this only stops *recording further TECs* and re-raises a raised error after
+    // the algorithm finishes running to completion. CPU work already in flight is
+    // not saved. Shared by every discovery entry point so the output-conversion
+    // contract stays in one place.
+    fn compute_discovery_tecs<A: TecAlgorithm<PointNDRf64>>(
+        algorithm: A,
+        point_set: &PointSet<PointNDRf64>,
+        dimensions: usize,
+        progress_callback: &Option<PyObject>,
+    ) -> PyResult<Vec<RawTec>> {
+        let stop_collecting = AtomicBool::new(false);
+        let mut raw_tecs: Vec<RawTec> = Vec::new();
+        let callback_error: RefCell<Option<PyErr>> = RefCell::new(None);
+
+        let on_output = |tec: Tec<PointNDRf64>| {
+            if stop_collecting.load(Ordering::Relaxed) {
+                return;
+            }
+
+            let pattern = pattern_to_raw(&tec.pattern, dimensions);
+            let translated_patterns = tec
+                .translators
+                .iter()
+                .map(|t| pattern_to_raw(&tec.pattern.translate(t), dimensions))
+                .collect();
+
+            raw_tecs.push(RawTec { pattern, translated_patterns });
+
+            if let Some(callback) = progress_callback {
+                let outcome = Python::with_gil(|py| {
+                    callback
+                        .call1(py, (raw_tecs.len(),))
+                        .and_then(|result| result.as_ref(py).is_true())
+                });
+
+                match outcome {
+                    Ok(true) => {}
+                    Ok(false) => stop_collecting.store(true, Ordering::Relaxed),
+                    Err(err) => {
+                        stop_collecting.store(true, Ordering::Relaxed);
+                        *callback_error.borrow_mut() = Some(err);
+                    }
+                }
+            }
+        };
+
+        algorithm.compute_tecs_to_output(point_set, on_output);
 
-        for row in np_array.as_array().rows() {
-            // Use the raw point from the third column as x
-            points.push(Point2DRf64::new(row[2], row[1]));
+        if let Some(err) = callback_error.into_inner() {
+            return Err(err);
         }
 
-        points
+        Ok(raw_tecs)
+    }
+
+    fn raw_tecs_to_arrays<'py>(
+        py: Python<'py>,
+        raw_tecs: Vec<RawTec>,
+        dimensions: usize,
+    ) -> Vec<(&'py PyArray2<f64>, Vec<&'py PyArray2<f64>>)> {
+        raw_tecs
+            .into_iter()
+            .map(|raw| {
+                let pat_array = raw_to_array(py, &raw.pattern, dimensions);
+                let translations = raw
+                    .translated_patterns
+                    .iter()
+                    .map(|t| raw_to_array(py, t, dimensions))
+                    .collect();
+
+                (pat_array, translations)
+            })
+            .collect()
     }
 
+    // The available `TecAlgorithm` implementors, with their algorithm-specific
+    // parameters already validated. Only SIATEC-C's compression pass is bounded by
+    // `max_ioi`; SIATEC and COSIATEC run uncompressed and take no such bound. SIA
+    // itself is not offered here: it is posemir's MTP algorithm, outputting maximal
+    // translatable patterns rather than TECs, so it does not implement
+    // `TecAlgorithm` and cannot be run through this entry point.
+    enum Discovery {
+        Siatec,
+        Cosiatec,
+        SiatecC(f64),
+    }
+
+    fn parse_discovery(algorithm: &str, max_ioi: Option<f64>) -> PyResult<Discovery> {
+        fn require_no_max_ioi(algorithm: &str, max_ioi: Option<f64>) -> PyResult<()> {
+            if max_ioi.is_some() {
+                return Err(PosemirInputError::new_err(format!(
+                    "{algorithm} does not take max_ioi"
+                )));
+            }
+
+            Ok(())
+        }
+
+        fn require_max_ioi(algorithm: &str, max_ioi: Option<f64>) -> PyResult<f64> {
+            let max_ioi = max_ioi.ok_or_else(|| {
+                PosemirInputError::new_err(format!("{algorithm} requires max_ioi"))
+            })?;
+            require_positive(max_ioi, "max_ioi")?;
+
+            Ok(max_ioi)
+        }
+
+        match algorithm.to_ascii_lowercase().as_str() {
+            "siatec" => {
+                require_no_max_ioi("siatec", max_ioi)?;
+                Ok(Discovery::Siatec)
+            }
+            "cosiatec" => {
+                require_no_max_ioi("cosiatec", max_ioi)?;
+                Ok(Discovery::Cosiatec)
+            }
+            "siatec_c" | "siatec-c" => Ok(Discovery::SiatecC(require_max_ioi(algorithm, max_ioi)?)),
+            other => Err(PosemirInputError::new_err(format!(
+                "unknown discovery algorithm: {other}"
+            ))),
+        }
+    }
+
+    fn numpy_array_to_points(np_array: &PyReadonlyArrayDyn<f64>, column_indices: &[usize]) -> PyResult<Vec<PointNDRf64>> {
+        require_non_empty_columns(column_indices)?;
+
+        let array = np_array.as_array();
+        require_2d(array.ndim())?;
+
+        let rows = array.nrows();
+        require_non_empty(rows)?;
+
+        let required_columns = column_indices.iter().copied().max().map_or(0, |m| m + 1);
+        require_columns(array.ncols(), required_columns)?;
+        require_max_dimensions(column_indices.len(), MAX_DIMENSIONS)?;
+
+        let mut points: Vec<PointNDRf64> = Vec::with_capacity(rows);
+
+        for row in array.rows() {
+            let components: Vec<f64> = column_indices.iter().map(|&c| row[c]).collect();
+            points.push(PointNDRf64::new(&components));
+        }
+
+        Ok(points)
+    }
+
+    /// Runs SIATEC-C discovery, returning each TEC as (pattern, translated_patterns).
+    ///
+    /// `progress_callback`, if given, is called with the number of TECs found so
+    /// far after each one; returning `False` (or raising) stops the callback from
+    /// being invoked again and further TECs from being recorded, but does not
+    /// cancel the underlying computation, which still runs to completion.
     #[pyfn(m)]
     #[pyo3(name = "run_siatec_c")]
+    #[pyo3(signature = (np_points_array, max_ioi, progress_callback=None, column_indices=None))]
     fn run_siatec_c<'py>(
         py: Python<'py>,
         np_points_array: PyReadonlyArrayDyn<f64>,
-        max_ioi: f64
-    ) -> Vec<(&'py PyArray2<f64>, Vec<&'py PyArray2<f64>>)> {
+        max_ioi: f64,
+        progress_callback: Option<PyObject>,
+        column_indices: Option<Vec<usize>>,
+    ) -> PyResult<Vec<(&'py PyArray2<f64>, Vec<&'py PyArray2<f64>>)>> {
+        require_positive(max_ioi, "max_ioi")?;
 
-        let point_set = PointSet::new(numpy_array_to_points(&np_points_array));
+        let column_indices = column_indices.unwrap_or_else(|| DEFAULT_COLUMN_INDICES.to_vec());
+        let dimensions = column_indices.len();
 
-        let mut patterns: Vec<(&PyArray2<f64>, Vec<&PyArray2<f64>>)> = Vec::new();
+        let point_set = PointSet::new(numpy_array_to_points(&np_points_array, &column_indices)?);
 
-        let on_output = |tec: Tec<Point2DRf64>| {
-            let pat_array = pattern_to_array(py, &tec.pattern);
-            let mut translations = Vec::with_capacity(tec.translators.len());
-            for t in &tec.translators {
-                translations.push(pattern_to_array(py, &tec.pattern.translate(t)));
-            }
+        let raw_tecs = py.allow_threads(|| {
+            compute_discovery_tecs(SiatecC { max_ioi }, &point_set, dimensions, &progress_callback)
+        })?;
 
-            patterns.push((pat_array, translations));
-        };
+        Ok(raw_tecs_to_arrays(py, raw_tecs, dimensions))
+    }
 
-        SiatecC{ max_ioi }.compute_tecs_to_output(&point_set, on_output);
+    /// Runs the named discovery algorithm ("siatec", "cosiatec" or "siatec_c"),
+    /// returning each TEC as (pattern, translated_patterns).
+    ///
+    /// `progress_callback`, if given, is called with the number of TECs found so
+    /// far after each one; returning `False` (or raising) stops the callback from
+    /// being invoked again and further TECs from being recorded, but does not
+    /// cancel the underlying computation, which still runs to completion.
+    #[pyfn(m)]
+    #[pyo3(name = "run_discovery")]
+    #[pyo3(signature = (np_points_array, algorithm, max_ioi=None, progress_callback=None, column_indices=None))]
+    fn run_discovery<'py>(
+        py: Python<'py>,
+        np_points_array: PyReadonlyArrayDyn<f64>,
+        algorithm: &str,
+        max_ioi: Option<f64>,
+        progress_callback: Option<PyObject>,
+        column_indices: Option<Vec<usize>>,
+    ) -> PyResult<Vec<(&'py PyArray2<f64>, Vec<&'py PyArray2<f64>>)>> {
+        let discovery = parse_discovery(algorithm, max_ioi)?;
+
+        let column_indices = column_indices.unwrap_or_else(|| DEFAULT_COLUMN_INDICES.to_vec());
+        let dimensions = column_indices.len();
+
+        let point_set = PointSet::new(numpy_array_to_points(&np_points_array, &column_indices)?);
+
+        let raw_tecs = py.allow_threads(|| match discovery {
+            Discovery::Siatec => compute_discovery_tecs(Siatec {}, &point_set, dimensions, &progress_callback),
+            Discovery::Cosiatec => {
+                compute_discovery_tecs(Cosiatec {}, &point_set, dimensions, &progress_callback)
+            }
+            Discovery::SiatecC(max_ioi) => {
+                compute_discovery_tecs(SiatecC { max_ioi }, &point_set, dimensions, &progress_callback)
+            }
+        })?;
 
-        patterns
+        Ok(raw_tecs_to_arrays(py, raw_tecs, dimensions))
     }
 
     #[pyfn(m)]
     #[pyo3(name = "find_occurrences")]
+    #[pyo3(signature = (query_points_array, np_points_array, column_indices=None))]
     fn find_occurrences<'py>(
         py: Python<'py>,
         query_points_array: PyReadonlyArrayDyn<f64>,
         np_points_array: PyReadonlyArrayDyn<f64>,
-    ) -> Vec<&'py PyArray2<f64>> {
-        let point_set = PointSet::new(numpy_array_to_points(&np_points_array));
-        let query_points = numpy_array_to_points(&query_points_array);
-        let query_point_refs: Vec<&Point2DRf64> = query_points.iter().map(|p| p).collect();
+        column_indices: Option<Vec<usize>>,
+    ) -> PyResult<Vec<&'py PyArray2<f64>>> {
+        let column_indices = column_indices.unwrap_or_else(|| DEFAULT_COLUMN_INDICES.to_vec());
+        let dimensions = column_indices.len();
+
+        let point_set = PointSet::new(numpy_array_to_points(&np_points_array, &column_indices)?);
+        let query_points = numpy_array_to_points(&query_points_array, &column_indices)?;
+        let query_point_refs: Vec<&PointNDRf64> = query_points.iter().map(|p| p).collect();
         let query = Pattern::new(&query_point_refs);
 
         let mut occurrences = Vec::new();
 
-        let on_output = |pat: Pattern<Point2DRf64>| occurrences.push(pattern_to_array(py, &pat));
+        let on_output = |pat: Pattern<PointNDRf64>| occurrences.push(pattern_to_array(py, &pat, dimensions));
         let pattern_matcher = ExactMatcher {};
         pattern_matcher.find_occurrences_with_callback(&query, &point_set, on_output);
 
-        occurrences
+        Ok(occurrences)
+    }
+
+    #[pyfn(m)]
+    #[pyo3(name = "find_occurrences_approx")]
+    #[pyo3(signature = (query_points_array, np_points_array, min_ratio, column_indices=None))]
+    fn find_occurrences_approx<'py>(
+        py: Python<'py>,
+        query_points_array: PyReadonlyArrayDyn<f64>,
+        np_points_array: PyReadonlyArrayDyn<f64>,
+        min_ratio: f64,
+        column_indices: Option<Vec<usize>>,
+    ) -> PyResult<Vec<(&'py PyArray2<f64>, f64)>> {
+        require_ratio(min_ratio, "min_ratio")?;
+
+        let column_indices = column_indices.unwrap_or_else(|| DEFAULT_COLUMN_INDICES.to_vec());
+        let dimensions = column_indices.len();
+
+        let dataset_points = numpy_array_to_points(&np_points_array, &column_indices)?;
+        let query_points = numpy_array_to_points(&query_points_array, &column_indices)?;
+
+        let occurrences = approx::find_occurrences_approx(&query_points, &dataset_points, min_ratio);
+
+        Ok(occurrences
+            .into_iter()
+            .map(|occurrence| {
+                let raw: Vec<Vec<f64>> = occurrence
+                    .points
+                    .iter()
+                    .map(|p| (0..dimensions).map(|c| p.component_f64(c).unwrap()).collect())
+                    .collect();
+
+                (raw_to_array(py, &raw, dimensions), occurrence.ratio)
+            })
+            .collect())
     }
 
+    m.add("PosemirInputError", py.get_type::<PosemirInputError>())?;
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,172 @@
+use posemir::point_set::point::Point;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+
+/// Upper bound on the number of components `PointNDRf64` can hold.
+///
+/// posemir's discovery algorithms (SIA, SIATEC, COSIATEC, SIATEC-C) sort the
+/// point set lexicographically and move points around by value, so `Point`
+/// bounds on `P: Copy + Ord`. A `Vec`-backed point can satisfy neither, so
+/// components are stored inline in a fixed-size array instead, with `len`
+/// tracking how many of them are actually in use.
+pub const MAX_DIMENSIONS: usize = 8;
+
+/// An `f64` point of runtime-configurable dimensionality, up to [`MAX_DIMENSIONS`].
+///
+/// `Point2DRf64` fixes the point to two components; this is the generalization
+/// used when callers need more (or fewer) components, e.g. pitch + onset +
+/// duration instead of just pitch + onset.
+#[derive(Clone, Copy, Debug)]
+pub struct PointNDRf64 {
+    components: [f64; MAX_DIMENSIONS],
+    len: usize,
+}
+
+impl PointNDRf64 {
+    /// Builds a point from `components`. Panics if `components.len()` exceeds
+    /// [`MAX_DIMENSIONS`]; callers that take dimensions from external input
+    /// (e.g. numpy column indices) must validate that bound up front.
+    pub fn new(components: &[f64]) -> Self {
+        assert!(
+            components.len() <= MAX_DIMENSIONS,
+            "point has {} components, at most {MAX_DIMENSIONS} are supported",
+            components.len()
+        );
+
+        let mut storage = [0.0; MAX_DIMENSIONS];
+        storage[..components.len()].copy_from_slice(components);
+
+        PointNDRf64 { components: storage, len: components.len() }
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.len
+    }
+
+    fn as_slice(&self) -> &[f64] {
+        &self.components[..self.len]
+    }
+
+    /// The point translated by `delta`, component-wise.
+    pub fn translate(&self, delta: &PointNDRf64) -> PointNDRf64 {
+        let mut storage = [0.0; MAX_DIMENSIONS];
+        for i in 0..self.len {
+            storage[i] = self.components[i] + delta.components[i];
+        }
+
+        PointNDRf64 { components: storage, len: self.len }
+    }
+
+    /// The translation vector from `other` to `self`, i.e. `self - other`.
+    pub fn difference(&self, other: &PointNDRf64) -> PointNDRf64 {
+        let mut storage = [0.0; MAX_DIMENSIONS];
+        for i in 0..self.len {
+            storage[i] = self.components[i] - other.components[i];
+        }
+
+        PointNDRf64 { components: storage, len: self.len }
+    }
+}
+
+impl PartialEq for PointNDRf64 {
+    // Bit-equality, not raw `==`, so this agrees with `Hash` (`to_bits()`) and
+    // `Ord` (`total_cmp`) — raw `f64` equality treats `0.0 == -0.0` but they
+    // hash and order differently, which would break the `Hash`/`Eq` and
+    // `Ord`/`Eq` contracts posemir's sorted, hashed point sets depend on.
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PointNDRf64 {}
+
+impl Hash for PointNDRf64 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for c in self.as_slice() {
+            c.to_bits().hash(state);
+        }
+    }
+}
+
+impl PartialOrd for PointNDRf64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PointNDRf64 {
+    /// Lexicographic order over the components, matching the total order the
+    /// discovery algorithms sort point sets by.
+    fn cmp(&self, other: &Self) -> Ordering {
+        for (a, b) in self.as_slice().iter().zip(other.as_slice()) {
+            match a.total_cmp(b) {
+                Ordering::Equal => continue,
+                ordering => return ordering,
+            }
+        }
+
+        self.len.cmp(&other.len)
+    }
+}
+
+impl Point for PointNDRf64 {
+    fn get_raw_x(&self) -> f64 {
+        self.components[0]
+    }
+
+    fn component_f64(&self, index: usize) -> Option<f64> {
+        self.as_slice().get(index).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_components_are_equal_and_hash_the_same() {
+        let a = PointNDRf64::new(&[1.0, 2.0, 3.0]);
+        let b = PointNDRf64::new(&[1.0, 2.0, 3.0]);
+
+        assert_eq!(a, b);
+
+        use std::collections::hash_map::DefaultHasher;
+
+        let mut ha = DefaultHasher::new();
+        let mut hb = DefaultHasher::new();
+        a.hash(&mut ha);
+        b.hash(&mut hb);
+
+        assert_eq!(ha.finish(), hb.finish());
+    }
+
+    #[test]
+    fn ordering_is_lexicographic() {
+        let a = PointNDRf64::new(&[1.0, 5.0]);
+        let b = PointNDRf64::new(&[1.0, 6.0]);
+        let c = PointNDRf64::new(&[2.0, 0.0]);
+
+        assert!(a < b);
+        assert!(b < c);
+        assert!(a < c);
+    }
+
+    #[test]
+    fn translate_and_difference_are_inverses() {
+        let p = PointNDRf64::new(&[1.0, 2.0, 3.0]);
+        let delta = PointNDRf64::new(&[0.5, -1.0, 2.0]);
+
+        let translated = p.translate(&delta);
+        assert_eq!(translated.difference(&delta), p);
+    }
+
+    #[test]
+    fn component_f64_reads_back_in_order() {
+        let p = PointNDRf64::new(&[4.0, 5.0, 6.0]);
+
+        assert_eq!(p.component_f64(0), Some(4.0));
+        assert_eq!(p.component_f64(1), Some(5.0));
+        assert_eq!(p.component_f64(2), Some(6.0));
+        assert_eq!(p.component_f64(3), None);
+    }
+}